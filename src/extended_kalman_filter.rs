@@ -0,0 +1,153 @@
+use nalgebra::{ArrayStorage, Const, Matrix};
+
+use crate::kalman_filter::{correct_with_innovation, ObservationMatrix, SimpleSquareMatrix, SimpleVector};
+
+/// **A Kalman filter for nonlinear systems, via on-the-fly linearization.**
+///
+/// [`LinearKalmanFilter`](crate::linear_kalman_filter::LinearKalmanFilter) needs a
+/// state-transition matrix `F` and observation matrix `H` up front. Many systems worth
+/// tracking - range/bearing to a target, orbital dynamics, anything with a rotation or a
+/// product of state variables in it - don't have one. The extended Kalman filter instead
+/// takes the nonlinear transition function `f(x, u, dt)` and measurement function `h(x)`
+/// directly, and linearizes them at the current estimate by numerically differentiating
+/// them, so the caller never has to hand-derive a Jacobian.
+///
+/// ## Usage
+///
+/// *On creation*
+/// * Initial state and covariance, as with `KalmanFilter`
+/// * Nonlinear state-transition function `f(x, u, dt) -> x'`
+/// * Nonlinear measurement function `h(x) -> z`
+/// * Process noise covariance `Q`
+///
+/// ### Further reading
+/// * Extended Kalman filter - https://en.wikipedia.org/wiki/Extended_Kalman_filter
+pub struct ExtendedKalmanFilter<
+    const STATE_FACTORS: usize,
+    const MEASUREMENT_FACTORS: usize,
+    const CONTROL_FACTORS: usize,
+> {
+    current_state: SimpleVector<STATE_FACTORS>,
+    current_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+    process_noise: SimpleSquareMatrix<STATE_FACTORS>,
+    transition_function: Box<
+        dyn Fn(SimpleVector<STATE_FACTORS>, SimpleVector<CONTROL_FACTORS>, f64) -> SimpleVector<STATE_FACTORS>,
+    >,
+    measurement_function: Box<dyn Fn(SimpleVector<STATE_FACTORS>) -> SimpleVector<MEASUREMENT_FACTORS>>,
+}
+
+
+
+impl<const STATE_FACTORS: usize, const MEASUREMENT_FACTORS: usize, const CONTROL_FACTORS: usize>
+    ExtendedKalmanFilter<STATE_FACTORS, MEASUREMENT_FACTORS, CONTROL_FACTORS>
+{
+    /// Creates a new ExtendedKalmanFilter from the starting state and variance of the system,
+    /// the nonlinear transition and measurement functions, and the process noise covariance.
+    pub fn new(
+        current_state: SimpleVector<STATE_FACTORS>,
+        current_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+        transition_function: impl Fn(SimpleVector<STATE_FACTORS>, SimpleVector<CONTROL_FACTORS>, f64) -> SimpleVector<STATE_FACTORS>
+            + 'static,
+        measurement_function: impl Fn(SimpleVector<STATE_FACTORS>) -> SimpleVector<MEASUREMENT_FACTORS> + 'static,
+        process_noise: SimpleSquareMatrix<STATE_FACTORS>,
+    ) -> Self {
+        Self {
+            current_state,
+            current_covariance,
+            process_noise,
+            transition_function: Box::new(transition_function),
+            measurement_function: Box::new(measurement_function),
+        }
+    }
+
+
+    /// Pushes the state of the filter forward by some time. Mutates the object.
+    /// Requires a time step, measurement, and the variance in that measurement.
+    pub fn step_with_measurement(
+        &mut self,
+        dt: f64,
+        control: &SimpleVector<CONTROL_FACTORS>,
+        measurement: &SimpleVector<MEASUREMENT_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+    ) {
+        self.step_time(dt, control);
+        self.apply_measurement(measurement, measurement_covariance);
+    }
+
+
+    /// Move time forward by a specified value in the model. The transition function is
+    /// linearized at the current state by central finite differences to get the Jacobian
+    /// `F = ∂f/∂x`, which is used to propagate the covariance `P = F P F' + Q` alongside the
+    /// nonlinear state update `x = f(x, u, dt)`.
+    pub fn step_time(&mut self, dt: f64, control: &SimpleVector<CONTROL_FACTORS>) {
+        let transition_jacobian = numerical_jacobian(&self.current_state, |x| {
+            (self.transition_function)(x, *control, dt)
+        });
+
+        self.current_state = (self.transition_function)(self.current_state, *control, dt);
+        self.current_covariance = transition_jacobian
+            * self.current_covariance
+            * transition_jacobian.transpose()
+            + self.process_noise;
+    }
+
+
+    /// Apply newly collected data to the model. The measurement function is linearized at
+    /// the current state by central finite differences to get the Jacobian `H = ∂h/∂x`, and
+    /// the nonlinear residual `z - h(x)` is corrected using the same gain/covariance math as
+    /// [`KalmanFilter`](crate::kalman_filter::KalmanFilter).
+    pub fn apply_measurement(
+        &mut self,
+        measurement: &SimpleVector<MEASUREMENT_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+    ) {
+        let observation_jacobian =
+            numerical_jacobian(&self.current_state, |x| (self.measurement_function)(x));
+        let innovation = measurement - (self.measurement_function)(self.current_state);
+
+        (self.current_state, self.current_covariance) = correct_with_innovation(
+            &self.current_state,
+            &self.current_covariance,
+            &innovation,
+            measurement_covariance,
+            &observation_jacobian,
+        );
+    }
+
+
+    /// Returns the current best estimate of the state of the system.
+    pub fn get_current_state(&self) -> SimpleVector<STATE_FACTORS> {
+        self.current_state
+    }
+    /// Returns the covariance matrix describing the uncertainty in the state of the system.
+    pub fn get_current_covariance(&self) -> SimpleSquareMatrix<STATE_FACTORS> {
+        self.current_covariance
+    }
+}
+
+
+
+/// Numerically differentiates `f` at `x` by central finite differences, forming the
+/// Jacobian column by column: perturbing `x_i` by `±ε` (scaled to `x_i`'s own magnitude so
+/// the step stays meaningful whether `x_i` is near zero or very large) and taking the
+/// centered difference quotient `(f(x+εe_i) - f(x-εe_i)) / 2ε`.
+fn numerical_jacobian<const IN: usize, const OUT: usize>(
+    x: &SimpleVector<IN>,
+    f: impl Fn(SimpleVector<IN>) -> SimpleVector<OUT>,
+) -> ObservationMatrix<OUT, IN> {
+    let mut jacobian = Matrix::<f64, Const<OUT>, Const<IN>, ArrayStorage<f64, OUT, IN>>::zeros();
+
+    for i in 0..IN {
+        let epsilon = f64::EPSILON.sqrt() * x[i].abs().max(1.0);
+
+        let mut x_plus = *x;
+        x_plus[i] += epsilon;
+        let mut x_minus = *x;
+        x_minus[i] -= epsilon;
+
+        let column = (f(x_plus) - f(x_minus)) / (2.0 * epsilon);
+        jacobian.set_column(i, &column);
+    }
+
+    jacobian
+}
@@ -4,6 +4,10 @@ use nalgebra::{ArrayStorage, Const, Matrix, Vector};
 
 pub type SimpleVector<const SIZE: usize> = Vector<f64, Const<SIZE>, ArrayStorage<f64, SIZE, 1>>;
 pub type SimpleSquareMatrix<const SIZE: usize> = Matrix<f64, Const<SIZE>, Const<SIZE>, ArrayStorage<f64, SIZE, SIZE>>;
+/// An observation (measurement) matrix `H`, mapping a `STATE_FACTORS`-dimensional state onto
+/// a `MEASUREMENT_FACTORS`-dimensional measurement.
+pub type ObservationMatrix<const MEASUREMENT_FACTORS: usize, const STATE_FACTORS: usize> =
+    Matrix<f64, Const<MEASUREMENT_FACTORS>, Const<STATE_FACTORS>, ArrayStorage<f64, MEASUREMENT_FACTORS, STATE_FACTORS>>;
 
 
 
@@ -44,7 +48,8 @@ pub type SimpleSquareMatrix<const SIZE: usize> = Matrix<f64, Const<SIZE>, Const<
 /// 
 /// ## Notes
 /// ### See also
-/// TODO: linear version.
+/// [`LinearKalmanFilter`](crate::linear_kalman_filter::LinearKalmanFilter), for systems whose
+/// evolution is already known to be linear and can be expressed as a state-transition matrix.
 /// ### Limitations
 /// **Distributions**
 /// To make the math possible, the error in all measurements and predictions is assumed
@@ -96,7 +101,7 @@ impl<const STATE_FACTORS: usize> KalmanFilter<STATE_FACTORS> {
     /// Requires a time step, measurement, and the variance in that measurement.
     pub fn step_with_measurement(
         &mut self,
-        dt: f64, 
+        dt: f64,
         measurement: &SimpleVector<STATE_FACTORS>,
         measurement_covariance: &SimpleSquareMatrix<STATE_FACTORS>
     ) {
@@ -105,6 +110,25 @@ impl<const STATE_FACTORS: usize> KalmanFilter<STATE_FACTORS> {
     }
 
 
+    /// Pushes the state of the filter forward by some time, like `.step_with_measurement`,
+    /// but tolerates a dropped sensor reading: pass `None` and the step falls back to
+    /// `.predict_only`, carrying the inflated prediction covariance forward instead of
+    /// forcing the caller to fabricate a measurement. Useful in a fixed-rate fusion loop
+    /// where a sensor doesn't report on every tick.
+    pub fn step(
+        &mut self,
+        dt: f64,
+        measurement: Option<(&SimpleVector<STATE_FACTORS>, &SimpleSquareMatrix<STATE_FACTORS>)>,
+    ) {
+        match measurement {
+            Some((measurement, measurement_covariance)) => {
+                self.step_with_measurement(dt, measurement, measurement_covariance)
+            }
+            None => self.predict_only(dt),
+        }
+    }
+
+
     /// Move time forward by a specified value in the model. This involves using the
     /// evolution function provided at construction.
     /// NOTE: calling this function two times with dt=0.5 may not be the same as
@@ -118,19 +142,54 @@ impl<const STATE_FACTORS: usize> KalmanFilter<STATE_FACTORS> {
     }
 
 
+    /// Advances the filter on model prediction alone, with no measurement to correct it.
+    /// Equivalent to `.step_time`; exists as the explicit, documented no-measurement case for
+    /// fusion loops that otherwise always call `.step_with_measurement`; see also `.step`,
+    /// which picks between the two based on whether a measurement arrived this tick.
+    pub fn predict_only(&mut self, dt: f64) {
+        self.step_time(dt);
+    }
+
+
     /// Apply newly collected data to the model. This involves merging the current
     /// predicted state of the system with the new data. This function does not include
     /// the passage of time. If this is desired, use .step_time or .step_with_measurement.
+    ///
+    /// This assumes the measurement lives in the same space as the full state, measured
+    /// directly (i.e. an implicit observation matrix of the identity). If the measurement
+    /// only observes part of the state, or observes some combination of state variables,
+    /// use .apply_partial_measurement instead.
     pub fn apply_measurement(
         &mut self,
         measurement: &SimpleVector<STATE_FACTORS>,
         measurement_covariance: &SimpleSquareMatrix<STATE_FACTORS>
     ) {
-        (self.current_state, self.current_covariance) = Self::combine_measurements(
-            &measurement,
-            &measurement_covariance,
+        (self.current_state, self.current_covariance) = correct(
+            &self.current_state,
+            &self.current_covariance,
+            measurement,
+            measurement_covariance,
+            &SimpleSquareMatrix::<STATE_FACTORS>::identity(),
+        );
+    }
+
+
+    /// Apply a measurement that only observes the state indirectly, through an observation
+    /// matrix `H`. This is the general form of .apply_measurement, and lets the measurement
+    /// have a different dimension (`MEASUREMENT_FACTORS`) than the state, e.g. measuring only
+    /// position while tracking position and velocity.
+    pub fn apply_partial_measurement<const MEASUREMENT_FACTORS: usize>(
+        &mut self,
+        measurement: &SimpleVector<MEASUREMENT_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+        observation_matrix: &ObservationMatrix<MEASUREMENT_FACTORS, STATE_FACTORS>,
+    ) {
+        (self.current_state, self.current_covariance) = correct(
             &self.current_state,
             &self.current_covariance,
+            measurement,
+            measurement_covariance,
+            observation_matrix,
         );
     }
 
@@ -143,30 +202,67 @@ impl<const STATE_FACTORS: usize> KalmanFilter<STATE_FACTORS> {
     pub fn get_current_covariance(&self) -> SimpleSquareMatrix<STATE_FACTORS> {
         self.current_covariance
     }
+}
 
 
-    /// Takes two measurements, with their variances, and determines the probability distribution
-    /// of what the true value is likely to be.
-    /// 
-    /// The math for this is fairly crazy. You have been warned.
-    /// The current implementation uses this math 
-    /// (https://math.stackexchange.com/questions/157172/product-of-two-multivariate-gaussians-distributions),
-    /// although it doesn't match numerical solutions. The prior and marginal probability
-    /// are currently assumed to be improper flat distributions.
-    fn combine_measurements(
-        mean_1: &SimpleVector<STATE_FACTORS>,
-        covariance_1: &SimpleSquareMatrix<STATE_FACTORS>,
-        mean_2: &SimpleVector<STATE_FACTORS>,
-        covariance_2: &SimpleSquareMatrix<STATE_FACTORS>,
-    ) -> (SimpleVector<STATE_FACTORS>, SimpleSquareMatrix<STATE_FACTORS>) {
-
-        let inverse_sum = 
-            (covariance_1 + covariance_2).try_inverse().expect("Singular covariance matrices are not allowed.");
-
-        (
-            covariance_2 * inverse_sum * mean_1 + covariance_1 * inverse_sum * mean_2,
-            covariance_1 * inverse_sum * covariance_2,
-        )
-        
-    }
+
+/// Corrects a predicted state and covariance using a measurement that relates to the
+/// state through an observation matrix `H`.
+///
+/// This is the standard Kalman correction step: the innovation `y = z - H x` is the
+/// difference between the measurement and what the model expects to observe, and the
+/// innovation covariance `S = H P H' + R` describes how uncertain that difference is.
+/// The Kalman gain `K = P H' S⁻¹` says how much to trust the innovation relative to the
+/// prediction, giving the updated state `x = x + K y`. The covariance is updated using
+/// the Joseph form `P = (I - K H) P (I - K H)' + K R K'`, which stays symmetric and
+/// positive semi-definite even when `K` isn't exactly optimal (e.g. due to floating point
+/// error), unlike the shorter `P = (I - K H) P`.
+///
+/// Used by `KalmanFilter` and `LinearKalmanFilter` for their measurement correction step.
+pub(crate) fn correct<const STATE_FACTORS: usize, const MEASUREMENT_FACTORS: usize>(
+    state: &SimpleVector<STATE_FACTORS>,
+    covariance: &SimpleSquareMatrix<STATE_FACTORS>,
+    measurement: &SimpleVector<MEASUREMENT_FACTORS>,
+    measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+    observation_matrix: &ObservationMatrix<MEASUREMENT_FACTORS, STATE_FACTORS>,
+) -> (SimpleVector<STATE_FACTORS>, SimpleSquareMatrix<STATE_FACTORS>) {
+    let innovation = measurement - observation_matrix * state;
+    correct_with_innovation(
+        state,
+        covariance,
+        &innovation,
+        measurement_covariance,
+        observation_matrix,
+    )
+}
+
+/// The Kalman gain and covariance update half of `correct`, parameterized directly on the
+/// innovation `y` rather than deriving it as `z - H x`. The extended filter computes `y`
+/// itself, via its nonlinear measurement function instead of `H x`, and shares this
+/// gain/covariance math. The unscented filter does not use it: it forms its gain from its
+/// sigma points instead.
+pub(crate) fn correct_with_innovation<const STATE_FACTORS: usize, const MEASUREMENT_FACTORS: usize>(
+    state: &SimpleVector<STATE_FACTORS>,
+    covariance: &SimpleSquareMatrix<STATE_FACTORS>,
+    innovation: &SimpleVector<MEASUREMENT_FACTORS>,
+    measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+    observation_matrix: &ObservationMatrix<MEASUREMENT_FACTORS, STATE_FACTORS>,
+) -> (SimpleVector<STATE_FACTORS>, SimpleSquareMatrix<STATE_FACTORS>) {
+    let observation_matrix_t = observation_matrix.transpose();
+
+    let innovation_covariance =
+        observation_matrix * covariance * &observation_matrix_t + measurement_covariance;
+
+    let kalman_gain = covariance * &observation_matrix_t * innovation_covariance
+        .try_inverse()
+        .expect("Singular innovation covariance matrices are not allowed.");
+
+    let updated_state = state + kalman_gain * innovation;
+
+    let covariance_factor =
+        SimpleSquareMatrix::<STATE_FACTORS>::identity() - kalman_gain * observation_matrix;
+    let updated_covariance = covariance_factor * covariance * covariance_factor.transpose()
+        + kalman_gain * measurement_covariance * kalman_gain.transpose();
+
+    (updated_state, updated_covariance)
 }
\ No newline at end of file
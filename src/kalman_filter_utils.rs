@@ -1,9 +1,7 @@
 use crate::kalman_filter::*;
 
 // TODO: (from issue #2)
-// * Replacing evolution function pointer with matrix.
 // * Replacing full covariance matrix with diagonal covariance matrix.
-// * Allowing a constant dt to be set on creation of filter struct.
 
 
 
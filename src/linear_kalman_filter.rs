@@ -0,0 +1,182 @@
+use nalgebra::{ArrayStorage, Const, Matrix};
+
+use crate::kalman_filter::{correct, ObservationMatrix, SimpleSquareMatrix, SimpleVector};
+use crate::smoother::KalmanTrajectory;
+
+/// A control matrix `B`, mapping a `CONTROL_FACTORS`-dimensional control input `u` onto a
+/// change in a `STATE_FACTORS`-dimensional state.
+pub type ControlMatrix<const STATE_FACTORS: usize, const CONTROL_FACTORS: usize> =
+    Matrix<f64, Const<STATE_FACTORS>, Const<CONTROL_FACTORS>, ArrayStorage<f64, STATE_FACTORS, CONTROL_FACTORS>>;
+
+
+
+/// **A Kalman filter specialized for linear systems.**
+///
+/// [`KalmanFilter`](crate::kalman_filter::KalmanFilter) takes an arbitrary evolution closure,
+/// which covers any system but forces the caller to hand-derive how the covariance propagates.
+/// Most tracking problems (constant velocity/acceleration models, and anything else of the
+/// form `x' = F x + B u`) don't need that generality: the state-transition matrix `F` and
+/// process noise `Q` already say everything the evolution closure would have computed.
+///
+/// ## Usage
+///
+/// *On creation*
+/// * Initial state and covariance, as with `KalmanFilter`
+/// * State-transition matrix `F`
+/// * Process noise covariance `Q`
+///
+/// *Throughout, as step inputs*
+/// * Changes in time from one step to next
+/// * Optionally, a control input `u`, if a control matrix `B` was supplied with `.with_control_matrix`
+///
+/// ### Further reading
+/// * Kalman filters - https://en.wikipedia.org/wiki/Kalman_filter
+pub struct LinearKalmanFilter<const STATE_FACTORS: usize, const CONTROL_FACTORS: usize> {
+    current_state: SimpleVector<STATE_FACTORS>,
+    current_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+    transition_matrix: SimpleSquareMatrix<STATE_FACTORS>,
+    process_noise: SimpleSquareMatrix<STATE_FACTORS>,
+    control_matrix: Option<ControlMatrix<STATE_FACTORS, CONTROL_FACTORS>>,
+}
+
+
+
+impl<const STATE_FACTORS: usize, const CONTROL_FACTORS: usize>
+    LinearKalmanFilter<STATE_FACTORS, CONTROL_FACTORS>
+{
+    /// Creates a new LinearKalmanFilter from the starting state and variance of the system,
+    /// along with the state-transition matrix `F` and process noise covariance `Q`.
+    pub fn new(
+        current_state: SimpleVector<STATE_FACTORS>,
+        current_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+        transition_matrix: SimpleSquareMatrix<STATE_FACTORS>,
+        process_noise: SimpleSquareMatrix<STATE_FACTORS>,
+    ) -> Self {
+        Self {
+            current_state,
+            current_covariance,
+            transition_matrix,
+            process_noise,
+            control_matrix: None,
+        }
+    }
+
+    /// Attaches a control matrix `B`, letting `.step_time_with_control` fold a control input
+    /// `u` into the predicted state as `x = F x + B u`.
+    pub fn with_control_matrix(
+        mut self,
+        control_matrix: ControlMatrix<STATE_FACTORS, CONTROL_FACTORS>,
+    ) -> Self {
+        self.control_matrix = Some(control_matrix);
+        self
+    }
+
+
+    /// Pushes the state of the filter forward by some time. Mutates the object.
+    /// Requires a time step, measurement, and the variance in that measurement.
+    pub fn step_with_measurement(
+        &mut self,
+        dt: f64,
+        measurement: &SimpleVector<STATE_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<STATE_FACTORS>,
+    ) {
+        self.step_time(dt);
+        self.apply_measurement(measurement, measurement_covariance);
+    }
+
+    /// Like `.step_with_measurement`, but also records the step into `trajectory` so that
+    /// [`smooth`](crate::smoother::smooth) can later produce a smoothed estimate for it using
+    /// every measurement in the run, not just those up to this step.
+    pub fn step_with_measurement_recorded(
+        &mut self,
+        dt: f64,
+        measurement: &SimpleVector<STATE_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<STATE_FACTORS>,
+        trajectory: &mut KalmanTrajectory<STATE_FACTORS>,
+    ) {
+        self.step_time(dt);
+        let predicted_state = self.current_state;
+        let predicted_covariance = self.current_covariance;
+
+        self.apply_measurement(measurement, measurement_covariance);
+
+        trajectory.record(
+            self.current_state,
+            self.current_covariance,
+            predicted_state,
+            predicted_covariance,
+            self.transition_matrix,
+        );
+    }
+
+
+    /// Move time forward by a specified value in the model, applying `x = F x`, `P = F P F' + Q`.
+    /// `F` and `Q` are assumed to already be parameterized for whatever `dt` is passed here;
+    /// this filter does not itself rescale them for a varying timestep.
+    pub fn step_time(&mut self, _dt: f64) {
+        self.current_state = self.transition_matrix * self.current_state;
+        self.current_covariance = self.transition_matrix
+            * self.current_covariance
+            * self.transition_matrix.transpose()
+            + self.process_noise;
+    }
+
+    /// Like `.step_time`, but also folds a control input `u` into the predicted state as
+    /// `x = F x + B u`, using the control matrix supplied via `.with_control_matrix`.
+    ///
+    /// Panics if no control matrix has been attached.
+    pub fn step_time_with_control(&mut self, dt: f64, control: &SimpleVector<CONTROL_FACTORS>) {
+        self.step_time(dt);
+        let control_matrix = self
+            .control_matrix
+            .as_ref()
+            .expect("step_time_with_control requires a control matrix; call .with_control_matrix first.");
+        self.current_state += control_matrix * control;
+    }
+
+
+    /// Apply newly collected data to the model. This involves merging the current
+    /// predicted state of the system with the new data. This function does not include
+    /// the passage of time. If this is desired, use .step_time or .step_with_measurement.
+    pub fn apply_measurement(
+        &mut self,
+        measurement: &SimpleVector<STATE_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<STATE_FACTORS>,
+    ) {
+        (self.current_state, self.current_covariance) = correct(
+            &self.current_state,
+            &self.current_covariance,
+            measurement,
+            measurement_covariance,
+            &SimpleSquareMatrix::<STATE_FACTORS>::identity(),
+        );
+    }
+
+    /// Apply a measurement that only observes the state indirectly, through an observation
+    /// matrix `H`. See [`KalmanFilter::apply_partial_measurement`](crate::kalman_filter::KalmanFilter::apply_partial_measurement)
+    /// for the general explanation.
+    pub fn apply_partial_measurement<const MEASUREMENT_FACTORS: usize>(
+        &mut self,
+        measurement: &SimpleVector<MEASUREMENT_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+        observation_matrix: &ObservationMatrix<MEASUREMENT_FACTORS, STATE_FACTORS>,
+    ) {
+        (self.current_state, self.current_covariance) = correct(
+            &self.current_state,
+            &self.current_covariance,
+            measurement,
+            measurement_covariance,
+            observation_matrix,
+        );
+    }
+
+
+    /// Returns the current best estimate of the state of the system.
+    pub fn get_current_state(&self) -> SimpleVector<STATE_FACTORS> {
+        self.current_state
+    }
+    /// Returns the covariance matrix describing the uncertainty in the state of the system.
+    pub fn get_current_covariance(&self) -> SimpleSquareMatrix<STATE_FACTORS> {
+        self.current_covariance
+    }
+}
@@ -0,0 +1,109 @@
+use crate::kalman_filter::{SimpleSquareMatrix, SimpleVector};
+
+/// One step of a recorded filter run: the filtered estimate produced at this step, the state
+/// predicted for this step from the previous step's estimate (before this step's measurement
+/// was applied), and the transition matrix `F` used to produce that prediction. Collected into
+/// a [`KalmanTrajectory`] and consumed by [`smooth`].
+pub struct KalmanTrajectoryStep<const STATE_FACTORS: usize> {
+    pub filtered_state: SimpleVector<STATE_FACTORS>,
+    pub filtered_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+    pub predicted_state: SimpleVector<STATE_FACTORS>,
+    pub predicted_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+    pub transition_matrix: SimpleSquareMatrix<STATE_FACTORS>,
+}
+
+
+
+/// A recorded sequence of filter steps from a single run, for later smoothing with
+/// [`smooth`]. Useful for users running the filter offline over logged sensor data, who want
+/// every estimate to benefit from the full run rather than only the measurements up to it.
+#[derive(Default)]
+pub struct KalmanTrajectory<const STATE_FACTORS: usize> {
+    steps: Vec<KalmanTrajectoryStep<STATE_FACTORS>>,
+}
+
+impl<const STATE_FACTORS: usize> KalmanTrajectory<STATE_FACTORS> {
+    /// Creates an empty trajectory to record into.
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Records one step of a filter run: the resulting filtered estimate, the state
+    /// predicted for this step from the previous one, and the transition matrix that
+    /// produced that prediction.
+    pub fn record(
+        &mut self,
+        filtered_state: SimpleVector<STATE_FACTORS>,
+        filtered_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+        predicted_state: SimpleVector<STATE_FACTORS>,
+        predicted_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+        transition_matrix: SimpleSquareMatrix<STATE_FACTORS>,
+    ) {
+        self.steps.push(KalmanTrajectoryStep {
+            filtered_state,
+            filtered_covariance,
+            predicted_state,
+            predicted_covariance,
+            transition_matrix,
+        });
+    }
+
+    /// The number of steps recorded so far.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+    /// Whether any steps have been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+
+
+/// Runs the Rauch–Tung–Striebel backward pass over a recorded trajectory, producing a
+/// smoothed `(state, covariance)` estimate for every step that incorporates every
+/// measurement in the run, not just those up to that point.
+///
+/// The smoother starts from the last filtered estimate - there's nothing later in the run to
+/// smooth it against - and works backward. At each step, `J_t = P_{t|t} F_t' P_{t+1|t}⁻¹`
+/// says how much of the gap between the already-smoothed next state and what the *next* step
+/// predicted for it should be folded back in, giving
+/// `x_{t|T} = x_{t|t} + J_t (x_{t+1|T} - x_{t+1|t})` and
+/// `P_{t|T} = P_{t|t} + J_t (P_{t+1|T} - P_{t+1|t}) J_t'`. Note that `F_t` (the transition out
+/// of step `t`) and `x_{t+1|t}`/`P_{t+1|t}` (the prediction into step `t+1`) both come from the
+/// *next* recorded step, since that's the one whose prediction was computed from step `t`.
+pub fn smooth<const STATE_FACTORS: usize>(
+    trajectory: &KalmanTrajectory<STATE_FACTORS>,
+) -> Vec<(SimpleVector<STATE_FACTORS>, SimpleSquareMatrix<STATE_FACTORS>)> {
+    let steps = &trajectory.steps;
+    if steps.is_empty() {
+        return Vec::new();
+    }
+
+    let last = steps.len() - 1;
+    let mut smoothed = vec![(steps[last].filtered_state, steps[last].filtered_covariance); steps.len()];
+
+    for t in (0..last).rev() {
+        let step = &steps[t];
+        let next_step = &steps[t + 1];
+
+        let smoother_gain = step.filtered_covariance
+            * next_step.transition_matrix.transpose()
+            * next_step
+                .predicted_covariance
+                .try_inverse()
+                .expect("Singular predicted covariance matrices are not allowed.");
+
+        let (next_smoothed_state, next_smoothed_covariance) = smoothed[t + 1];
+
+        smoothed[t] = (
+            step.filtered_state + smoother_gain * (next_smoothed_state - next_step.predicted_state),
+            step.filtered_covariance
+                + smoother_gain
+                    * (next_smoothed_covariance - next_step.predicted_covariance)
+                    * smoother_gain.transpose(),
+        );
+    }
+
+    smoothed
+}
@@ -0,0 +1,242 @@
+use crate::kalman_filter::{SimpleSquareMatrix, SimpleVector};
+
+/// **A Kalman filter for strongly nonlinear systems, via the unscented transform.**
+///
+/// [`ExtendedKalmanFilter`](crate::extended_kalman_filter::ExtendedKalmanFilter) linearizes
+/// the transition and measurement functions at a single point (the current estimate), which
+/// works poorly when the system is nonlinear enough that the linearization doesn't hold over
+/// the size of the current uncertainty. The unscented Kalman filter instead propagates a
+/// small, deterministic set of "sigma points" - chosen so their weighted mean and covariance
+/// exactly match the current estimate - through the nonlinear functions unchanged, and
+/// reconstructs the mean and covariance from the transformed points. This captures the
+/// nonlinearity's effect on the distribution without ever computing a derivative.
+///
+/// ## Usage
+///
+/// *On creation*
+/// * Initial state and covariance, as with `KalmanFilter`
+/// * Nonlinear state-transition function `f(x, dt) -> x'`
+/// * Nonlinear measurement function `h(x) -> z`
+/// * Process noise covariance `Q`
+///
+/// ### Further reading
+/// * Unscented Kalman filter - https://en.wikipedia.org/wiki/Kalman_filter#Unscented_Kalman_filter
+pub struct UnscentedKalmanFilter<const STATE_FACTORS: usize, const MEASUREMENT_FACTORS: usize> {
+    current_state: SimpleVector<STATE_FACTORS>,
+    current_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+    process_noise: SimpleSquareMatrix<STATE_FACTORS>,
+    transition_function: Box<dyn Fn(SimpleVector<STATE_FACTORS>, f64) -> SimpleVector<STATE_FACTORS>>,
+    measurement_function: Box<dyn Fn(SimpleVector<STATE_FACTORS>) -> SimpleVector<MEASUREMENT_FACTORS>>,
+    alpha: f64,
+    kappa: f64,
+    beta: f64,
+}
+
+
+
+impl<const STATE_FACTORS: usize, const MEASUREMENT_FACTORS: usize>
+    UnscentedKalmanFilter<STATE_FACTORS, MEASUREMENT_FACTORS>
+{
+    /// Creates a new UnscentedKalmanFilter from the starting state and variance of the
+    /// system, the nonlinear transition and measurement functions, and the process noise
+    /// covariance. Uses the typical sigma point spread of `alpha = 1e-3`, `kappa = 0`,
+    /// `beta = 2`; use `.with_parameters` to override these.
+    pub fn new(
+        current_state: SimpleVector<STATE_FACTORS>,
+        current_covariance: SimpleSquareMatrix<STATE_FACTORS>,
+        transition_function: impl Fn(SimpleVector<STATE_FACTORS>, f64) -> SimpleVector<STATE_FACTORS> + 'static,
+        measurement_function: impl Fn(SimpleVector<STATE_FACTORS>) -> SimpleVector<MEASUREMENT_FACTORS> + 'static,
+        process_noise: SimpleSquareMatrix<STATE_FACTORS>,
+    ) -> Self {
+        Self {
+            current_state,
+            current_covariance,
+            process_noise,
+            transition_function: Box::new(transition_function),
+            measurement_function: Box::new(measurement_function),
+            alpha: 1e-3,
+            kappa: 0.0,
+            beta: 2.0,
+        }
+    }
+
+    /// Overrides the sigma point spread parameters `alpha`, `kappa`, and `beta`.
+    pub fn with_parameters(mut self, alpha: f64, kappa: f64, beta: f64) -> Self {
+        self.alpha = alpha;
+        self.kappa = kappa;
+        self.beta = beta;
+        self
+    }
+
+
+    /// Pushes the state of the filter forward by some time. Mutates the object.
+    /// Requires a time step, measurement, and the variance in that measurement.
+    pub fn step_with_measurement(
+        &mut self,
+        dt: f64,
+        measurement: &SimpleVector<MEASUREMENT_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+    ) {
+        self.step_time(dt);
+        self.apply_measurement(measurement, measurement_covariance);
+    }
+
+
+    /// Move time forward by a specified value in the model. Sigma points are drawn from the
+    /// current mean and covariance, pushed through the nonlinear transition function, and
+    /// recombined into a predicted mean and covariance (`+ Q`).
+    pub fn step_time(&mut self, dt: f64) {
+        let sigma_points = self.sigma_points(&self.current_state, &self.current_covariance);
+        let propagated: Vec<_> = sigma_points
+            .iter()
+            .map(|chi| (self.transition_function)(*chi, dt))
+            .collect();
+
+        let (mean, covariance) = self.weighted_mean_and_covariance(&propagated);
+        self.current_state = mean;
+        self.current_covariance = covariance + self.process_noise;
+    }
+
+
+    /// Apply newly collected data to the model. Sigma points are drawn from the predicted
+    /// mean and covariance, pushed through the nonlinear measurement function, and used to
+    /// form the innovation covariance and the cross-covariance between state and measurement,
+    /// from which the Kalman gain is derived.
+    pub fn apply_measurement(
+        &mut self,
+        measurement: &SimpleVector<MEASUREMENT_FACTORS>,
+        measurement_covariance: &SimpleSquareMatrix<MEASUREMENT_FACTORS>,
+    ) {
+        let sigma_points = self.sigma_points(&self.current_state, &self.current_covariance);
+        let predicted_measurements: Vec<_> = sigma_points
+            .iter()
+            .map(|chi| (self.measurement_function)(*chi))
+            .collect();
+
+        let weights = self.weights();
+        let predicted_measurement_mean = weighted_mean(&weights, &predicted_measurements);
+
+        let mut innovation_covariance = *measurement_covariance;
+        let mut cross_covariance = SimpleSquareCrossMatrix::<STATE_FACTORS, MEASUREMENT_FACTORS>::zeros();
+        for i in 0..sigma_points.len() {
+            let state_diff = sigma_points[i] - self.current_state;
+            let measurement_diff = predicted_measurements[i] - predicted_measurement_mean;
+            innovation_covariance += measurement_diff * measurement_diff.transpose() * weights.covariance[i];
+            cross_covariance += state_diff * measurement_diff.transpose() * weights.covariance[i];
+        }
+
+        let kalman_gain = cross_covariance
+            * innovation_covariance
+                .try_inverse()
+                .expect("Singular innovation covariance matrices are not allowed.");
+
+        let innovation = measurement - predicted_measurement_mean;
+        self.current_state += kalman_gain * innovation;
+        self.current_covariance -= kalman_gain * innovation_covariance * kalman_gain.transpose();
+    }
+
+
+    /// Returns the current best estimate of the state of the system.
+    pub fn get_current_state(&self) -> SimpleVector<STATE_FACTORS> {
+        self.current_state
+    }
+    /// Returns the covariance matrix describing the uncertainty in the state of the system.
+    pub fn get_current_covariance(&self) -> SimpleSquareMatrix<STATE_FACTORS> {
+        self.current_covariance
+    }
+
+
+    /// The scaling factor `λ = α²(N+κ) - N` used throughout the unscented transform.
+    fn lambda(&self) -> f64 {
+        let n = STATE_FACTORS as f64;
+        self.alpha * self.alpha * (n + self.kappa) - n
+    }
+
+    /// Generates the `2N+1` sigma points for a given mean and covariance: the mean itself,
+    /// then the mean shifted by each column of `sqrt((N+λ) P)` (via its Cholesky factor) and
+    /// shifted by the negation of each column.
+    fn sigma_points(
+        &self,
+        mean: &SimpleVector<STATE_FACTORS>,
+        covariance: &SimpleSquareMatrix<STATE_FACTORS>,
+    ) -> Vec<SimpleVector<STATE_FACTORS>> {
+        let n = STATE_FACTORS as f64;
+        let lambda = self.lambda();
+
+        let scaled_covariance = covariance * (n + lambda);
+        let sqrt_covariance = scaled_covariance
+            .cholesky()
+            .expect("Covariance matrix must be positive definite to take sigma points.")
+            .l();
+
+        let mut points = Vec::with_capacity(2 * STATE_FACTORS + 1);
+        points.push(*mean);
+        for i in 0..STATE_FACTORS {
+            let offset = sqrt_covariance.column(i).into_owned();
+            points.push(mean + offset);
+        }
+        for i in 0..STATE_FACTORS {
+            let offset = sqrt_covariance.column(i).into_owned();
+            points.push(mean - offset);
+        }
+        points
+    }
+
+    /// The mean and mean/covariance weights for the `2N+1` sigma points.
+    fn weights(&self) -> UnscentedWeights {
+        let n = STATE_FACTORS as f64;
+        let lambda = self.lambda();
+
+        let mut mean = vec![1.0 / (2.0 * (n + lambda)); 2 * STATE_FACTORS + 1];
+        let mut covariance = mean.clone();
+
+        mean[0] = lambda / (n + lambda);
+        covariance[0] = mean[0] + (1.0 - self.alpha * self.alpha + self.beta);
+
+        UnscentedWeights { mean, covariance }
+    }
+
+    /// Recombines a set of propagated sigma points into a weighted mean and covariance.
+    fn weighted_mean_and_covariance<const FACTORS: usize>(
+        &self,
+        points: &[SimpleVector<FACTORS>],
+    ) -> (SimpleVector<FACTORS>, SimpleSquareMatrix<FACTORS>) {
+        let weights = self.weights();
+        let mean = weighted_mean(&weights, points);
+
+        let mut covariance = SimpleSquareMatrix::<FACTORS>::zeros();
+        for (point, weight) in points.iter().zip(weights.covariance.iter()) {
+            let diff = point - mean;
+            covariance += diff * diff.transpose() * *weight;
+        }
+
+        (mean, covariance)
+    }
+}
+
+/// The mean and mean/covariance weights for the `2N+1` unscented sigma points.
+struct UnscentedWeights {
+    mean: Vec<f64>,
+    covariance: Vec<f64>,
+}
+
+/// Recombines a set of sigma points into a weighted mean using the mean weights.
+fn weighted_mean<const FACTORS: usize>(
+    weights: &UnscentedWeights,
+    points: &[SimpleVector<FACTORS>],
+) -> SimpleVector<FACTORS> {
+    let mut mean = SimpleVector::<FACTORS>::zeros();
+    for (point, weight) in points.iter().zip(weights.mean.iter()) {
+        mean += point * *weight;
+    }
+    mean
+}
+
+/// A state-by-measurement cross-covariance matrix, as accumulated during the measurement update.
+type SimpleSquareCrossMatrix<const STATE_FACTORS: usize, const MEASUREMENT_FACTORS: usize> =
+    nalgebra::Matrix<
+        f64,
+        nalgebra::Const<STATE_FACTORS>,
+        nalgebra::Const<MEASUREMENT_FACTORS>,
+        nalgebra::ArrayStorage<f64, STATE_FACTORS, MEASUREMENT_FACTORS>,
+    >;
@@ -1,4 +1,8 @@
 use kalman_filter::*;
+use kalman_filter::extended_kalman_filter::ExtendedKalmanFilter;
+use kalman_filter::linear_kalman_filter::LinearKalmanFilter;
+use kalman_filter::smoother::{smooth, KalmanTrajectory};
+use kalman_filter::unscented_kalman_filter::UnscentedKalmanFilter;
 use nalgebra::*;
 
 fn assert_float_eq(expected:f64, actual:f64, margin: f64) {
@@ -39,9 +43,8 @@ fn simple_1d() {
 
 	// Step 1
 	filter.step(
-		0.1, 
-		&Vector1::new(2.0), 
-		&Matrix1::new(2.0)
+		0.1,
+		Some((&Vector1::new(2.0), &Matrix1::new(2.0)))
 	);
 
 	assert_float_eq(0.6666666666666666, *filter.get_current_state().index(0), 1e-6);
@@ -49,11 +52,194 @@ fn simple_1d() {
 
 	// Step 2
 	filter.step(
-		0.1, 
-		&Vector1::new(2.0), 
-		&Matrix1::new(2.0)
+		0.1,
+		Some((&Vector1::new(2.0), &Matrix1::new(2.0)))
 	);
 
 	assert_float_eq(1.0, *filter.get_current_state().index(0), 1e-6);
 	assert_float_eq(0.5, *filter.get_current_covariance().index(0), 1e-6);
-}
\ No newline at end of file
+}
+
+/// Tests that a dropped measurement (`None`) falls back to pure prediction, carrying the
+/// state and inflated covariance forward without a correction.
+#[test]
+fn missing_measurement_predicts_only() {
+	fn evolution_func(
+        state: SimpleVector<1>,
+        covar: SimpleSquareMatrix<1>,
+        _: f64
+    ) -> (
+        SimpleVector<1>,
+        SimpleSquareMatrix<1>
+    ) {
+		(state, covar + Matrix1::new(0.1))
+	}
+
+	let mut filter = KalmanFilter::new(
+		Vector1::new(3.0),
+		Matrix1::new(1.0),
+		evolution_func
+	);
+
+	filter.step(0.1, None);
+
+	assert_float_eq(3.0, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(1.1, *filter.get_current_covariance().index(0), 1e-6);
+}
+
+/// Tests `.apply_partial_measurement` by fusing a position-only reading into a
+/// position+velocity state. With an initially diagonal (uncorrelated) covariance, the
+/// update should behave exactly like the 1D case in `simple_1d` for the position component,
+/// while leaving the unobserved velocity component untouched.
+#[test]
+fn partial_measurement_position_only() {
+	fn evolution_func(
+        state: SimpleVector<2>,
+        covar: SimpleSquareMatrix<2>,
+        _: f64
+    ) -> (
+        SimpleVector<2>,
+        SimpleSquareMatrix<2>
+    ) {
+		(state, covar)
+	}
+
+	let mut filter = KalmanFilter::new(
+		Vector2::new(0.0, 1.0),
+		Matrix2::identity(),
+		evolution_func
+	);
+
+	// Observe only position (the first state variable).
+	let observation_matrix: ObservationMatrix<1, 2> = Matrix1x2::new(1.0, 0.0);
+
+	filter.step_time(0.1);
+	filter.apply_partial_measurement(
+		&Vector1::new(2.0),
+		&Matrix1::new(2.0),
+		&observation_matrix
+	);
+
+	// Position behaves exactly like the fully-observed 1D case.
+	assert_float_eq(0.6666666666666666, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.6666666666666666, *filter.get_current_covariance().index((0, 0)), 1e-6);
+	// Velocity is unobserved, so it's untouched by the measurement.
+	assert_float_eq(1.0, *filter.get_current_state().index(1), 1e-6);
+	assert_float_eq(1.0, *filter.get_current_covariance().index((1, 1)), 1e-6);
+}
+
+/// Tests `LinearKalmanFilter` against the same externally-verified values as `simple_1d`:
+/// with `F = 1` and `Q = 0`, matrix-based prediction should behave identically to the
+/// identity evolution closure used there.
+#[test]
+fn linear_filter_matches_simple_1d() {
+	let mut filter = LinearKalmanFilter::<1, 1>::new(
+		Vector1::new(0.0),
+		Matrix1::new(1.0),
+		Matrix1::new(1.0),
+		Matrix1::new(0.0),
+	);
+
+	filter.step_with_measurement(0.1, &Vector1::new(2.0), &Matrix1::new(2.0));
+
+	assert_float_eq(0.6666666666666666, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.6666666666666666, *filter.get_current_covariance().index(0), 1e-6);
+
+	filter.step_with_measurement(0.1, &Vector1::new(2.0), &Matrix1::new(2.0));
+
+	assert_float_eq(1.0, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.5, *filter.get_current_covariance().index(0), 1e-6);
+}
+
+/// Tests `ExtendedKalmanFilter` against the same externally-verified values as `simple_1d`.
+/// With transition and measurement functions that are already linear (the identity), the
+/// numerically differentiated Jacobians reduce to `1`, so the filter should behave
+/// identically to the identity evolution closure used there.
+#[test]
+fn extended_filter_matches_simple_1d() {
+	let mut filter = ExtendedKalmanFilter::<1, 1, 1>::new(
+		Vector1::new(0.0),
+		Matrix1::new(1.0),
+		|state, _control: SimpleVector<1>, _dt| state,
+		|state| state,
+		Matrix1::new(0.0),
+	);
+
+	filter.step_with_measurement(0.1, &Vector1::new(0.0), &Vector1::new(2.0), &Matrix1::new(2.0));
+
+	assert_float_eq(0.6666666666666666, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.6666666666666666, *filter.get_current_covariance().index(0), 1e-6);
+
+	filter.step_with_measurement(0.1, &Vector1::new(0.0), &Vector1::new(2.0), &Matrix1::new(2.0));
+
+	assert_float_eq(1.0, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.5, *filter.get_current_covariance().index(0), 1e-6);
+}
+
+/// Tests `UnscentedKalmanFilter` against the same externally-verified values as `simple_1d`.
+/// The sigma-point transform is exact for linear functions, so an identity transition and
+/// measurement function should reproduce the identity evolution closure's results exactly.
+#[test]
+fn unscented_filter_matches_simple_1d() {
+	let mut filter = UnscentedKalmanFilter::<1, 1>::new(
+		Vector1::new(0.0),
+		Matrix1::new(1.0),
+		|state, _dt| state,
+		|state| state,
+		Matrix1::new(0.0),
+	);
+
+	filter.step_with_measurement(0.1, &Vector1::new(2.0), &Matrix1::new(2.0));
+
+	assert_float_eq(0.6666666666666666, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.6666666666666666, *filter.get_current_covariance().index(0), 1e-6);
+
+	filter.step_with_measurement(0.1, &Vector1::new(2.0), &Matrix1::new(2.0));
+
+	assert_float_eq(1.0, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.5, *filter.get_current_covariance().index(0), 1e-6);
+}
+
+/// Tests the RTS `smooth` pass against a hand-derived trajectory. With `F = 1` and `Q = 0`
+/// (a perfectly static system), the final filtered estimate is also the best possible
+/// estimate of every earlier step, so smoothing should pull the first step's estimate all
+/// the way to the final filtered estimate - not leave it at its original, worse value.
+#[test]
+fn smoother_improves_earlier_estimate() {
+	let mut filter = LinearKalmanFilter::<1, 1>::new(
+		Vector1::new(0.0),
+		Matrix1::new(1.0),
+		Matrix1::new(1.0),
+		Matrix1::new(0.0),
+	);
+	let mut trajectory = KalmanTrajectory::<1>::new();
+
+	filter.step_with_measurement_recorded(
+		0.1,
+		&Vector1::new(2.0),
+		&Matrix1::new(2.0),
+		&mut trajectory,
+	);
+	// Filtered (forward-only) estimate, reproduced from `simple_1d`.
+	assert_float_eq(0.6666666666666666, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.6666666666666666, *filter.get_current_covariance().index(0), 1e-6);
+
+	filter.step_with_measurement_recorded(
+		0.1,
+		&Vector1::new(2.0),
+		&Matrix1::new(2.0),
+		&mut trajectory,
+	);
+	assert_float_eq(1.0, *filter.get_current_state().index(0), 1e-6);
+	assert_float_eq(0.5, *filter.get_current_covariance().index(0), 1e-6);
+
+	let smoothed = smooth(&trajectory);
+	assert_eq!(smoothed.len(), 2);
+
+	// The static system means the first step's smoothed estimate should match the final
+	// filtered estimate exactly, rather than staying at its original, less certain value.
+	assert_float_eq(1.0, *smoothed[0].0.index(0), 1e-6);
+	assert_float_eq(0.5, *smoothed[0].1.index(0), 1e-6);
+	assert_float_eq(1.0, *smoothed[1].0.index(0), 1e-6);
+	assert_float_eq(0.5, *smoothed[1].1.index(0), 1e-6);
+}